@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// Errors that can occur anywhere in the OAuth 2.0 / OIDC authentication flow
+#[derive(Debug)]
+pub enum AuthError {
+    /// The request to a provider endpoint (token, discovery, JWKS, device
+    /// authorization, revocation) itself failed (connection refused, TLS error,
+    /// timeout, etc.)
+    NetworkError(String),
+    /// The flow or provider is misconfigured, or the provider sent back
+    /// something Himalaya isn't willing to proceed with (e.g. a state
+    /// mismatch, a malformed redirect, a discovery document that can't be used)
+    ConfigError(String),
+    /// The token, device-authorization or revocation endpoint rejected the
+    /// request, or returned a response Himalaya couldn't parse
+    TokenExchangeFailed(String),
+    /// An `id_token`'s signature or standard claims (`iss`, `aud`, `exp`, `nonce`)
+    /// failed verification
+    IdTokenValidationFailed(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::NetworkError(msg) => write!(f, "network error: {}", msg),
+            AuthError::ConfigError(msg) => write!(f, "configuration error: {}", msg),
+            AuthError::TokenExchangeFailed(msg) => write!(f, "token exchange failed: {}", msg),
+            AuthError::IdTokenValidationFailed(msg) => write!(f, "id_token validation failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
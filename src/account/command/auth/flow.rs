@@ -11,16 +11,101 @@ pub struct OAuthTokens {
     pub access_token: String,
     pub refresh_token: Option<String>,
     pub expires_in: Option<u64>,
+    pub id_token: Option<String>,
+}
+
+impl OAuthTokens {
+    /// Safety margin subtracted from `expires_in` so callers refresh slightly
+    /// before the provider actually invalidates the token
+    const EXPIRY_SAFETY_MARGIN_SECS: u64 = 60;
+
+    /// Whether the access token is expired (or about to expire) as of `obtained_at`
+    ///
+    /// Tokens with no `expires_in` are treated as never expiring, since some
+    /// providers don't advertise a lifetime at all.
+    pub fn is_expired(&self, obtained_at: std::time::SystemTime) -> bool {
+        let Some(expires_in) = self.expires_in else {
+            return false;
+        };
+
+        let expires_in = expires_in.saturating_sub(Self::EXPIRY_SAFETY_MARGIN_SECS);
+        match obtained_at.elapsed() {
+            Ok(elapsed) => elapsed.as_secs() >= expires_in,
+            Err(_) => false,
+        }
+    }
+}
+
+/// How the client authenticates itself to the token endpoint (RFC 6749 §2.3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientAuthMethod {
+    /// `client_id`/`client_secret` sent in the request body (default in this crate's older code paths)
+    ClientSecretPost,
+    /// `client_id`/`client_secret` sent as an `Authorization: Basic` header
+    ClientSecretBasic,
+    /// Public client: no client secret at all, only `client_id` in the body
+    None,
+}
+
+/// Credentials to place in the token request body, given the configured [`ClientAuthMethod`]
+///
+/// `ClientSecretBasic` sends credentials via the `Authorization` header instead, so both
+/// fields are omitted from the body in that case. Shared by [`OAuthFlow`] and [`DeviceFlow`],
+/// since both authenticate to the same token endpoint the same way.
+fn body_credentials(
+    client_id: &str,
+    client_secret: &str,
+    auth_method: ClientAuthMethod,
+) -> (Option<String>, Option<String>) {
+    match auth_method {
+        ClientAuthMethod::ClientSecretPost => (Some(client_id.to_string()), Some(client_secret.to_string())),
+        ClientAuthMethod::ClientSecretBasic => (None, None),
+        ClientAuthMethod::None => (Some(client_id.to_string()), None),
+    }
+}
+
+/// Apply `Authorization: Basic` header auth when the provider requires `client_secret_basic`
+fn apply_client_auth(
+    client_id: &str,
+    client_secret: &str,
+    auth_method: ClientAuthMethod,
+    builder: reqwest::RequestBuilder,
+) -> reqwest::RequestBuilder {
+    match auth_method {
+        ClientAuthMethod::ClientSecretBasic => builder.basic_auth(client_id, Some(client_secret)),
+        ClientAuthMethod::ClientSecretPost | ClientAuthMethod::None => builder,
+    }
+}
+
+/// Attach a token request body, using `application/x-www-form-urlencoded` by default
+/// per RFC 6749, or JSON for providers that opt into it
+fn encode_token_request<T: Serialize>(
+    config: &ProviderConfig,
+    builder: reqwest::RequestBuilder,
+    request: &T,
+) -> reqwest::RequestBuilder {
+    if config.use_json_token_requests {
+        builder.json(request)
+    } else {
+        builder.form(request)
+    }
 }
 
 /// Token request for authorization code exchange
+///
+/// `client_id`/`client_secret` are omitted here when [`ClientAuthMethod::ClientSecretBasic`]
+/// puts them in the `Authorization` header instead.
 #[derive(Debug, Serialize)]
 struct TokenRequest {
-    client_id: String,
-    client_secret: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
     code: String,
     grant_type: String,
     code_verifier: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redirect_uri: Option<String>,
 }
 
 /// Token response from token endpoint
@@ -31,6 +116,290 @@ struct TokenResponse {
     refresh_token: Option<String>,
     #[serde(default)]
     expires_in: Option<u64>,
+    #[serde(default)]
+    id_token: Option<String>,
+}
+
+/// OpenID Connect discovery metadata document, as published at
+/// `<issuer>/.well-known/openid-configuration` (OIDC Discovery 1.0)
+#[derive(Debug, Deserialize)]
+pub struct OidcDiscoveryDocument {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub revocation_endpoint: Option<String>,
+    #[serde(default)]
+    pub jwks_uri: Option<String>,
+    #[serde(default)]
+    pub scopes_supported: Vec<String>,
+    /// `None` means the provider didn't advertise this field at all, which OIDC
+    /// Discovery 1.0 allows even for issuers that fully support PKCE S256
+    /// (e.g. Google). Treat that as "unknown" rather than "unsupported".
+    #[serde(default)]
+    pub code_challenge_methods_supported: Option<Vec<String>>,
+}
+
+impl OidcDiscoveryDocument {
+    /// Fetch and parse the discovery document for an OIDC issuer
+    pub async fn discover(issuer: &str) -> Result<Self, AuthError> {
+        let issuer = issuer.trim_end_matches('/');
+        let discovery_url = format!("{}/.well-known/openid-configuration", issuer);
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&discovery_url)
+            .send()
+            .await
+            .map_err(|e| AuthError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(AuthError::ConfigError(format!(
+                "OIDC discovery failed for {}: HTTP {}",
+                issuer,
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| AuthError::ConfigError(format!("Failed to parse OIDC discovery document: {}", e)))
+    }
+
+    /// Whether the provider advertises PKCE `S256` support, required before we
+    /// send a `code_challenge_method=S256` in the authorization request
+    ///
+    /// An absent `code_challenge_methods_supported` is treated as "unknown"
+    /// and does not block PKCE S256 usage; only a list that's present but
+    /// missing `S256` counts as an explicit "unsupported".
+    pub fn supports_pkce_s256(&self) -> bool {
+        match &self.code_challenge_methods_supported {
+            Some(methods) => methods.iter().any(|method| method == "S256"),
+            None => true,
+        }
+    }
+
+    /// Build a [`ProviderConfig`] from the discovered metadata
+    pub fn into_provider_config(self) -> Result<ProviderConfig, AuthError> {
+        if !self.supports_pkce_s256() {
+            return Err(AuthError::ConfigError(format!(
+                "Issuer {} explicitly does not advertise PKCE S256 support",
+                self.issuer
+            )));
+        }
+
+        Ok(ProviderConfig::from_oidc_discovery(
+            self.authorization_endpoint,
+            self.token_endpoint,
+            self.revocation_endpoint,
+            self.jwks_uri,
+            self.issuer,
+            self.scopes_supported,
+        ))
+    }
+}
+
+/// Token request for the refresh-token grant
+#[derive(Debug, Serialize)]
+struct RefreshTokenRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    refresh_token: String,
+    grant_type: String,
+}
+
+/// A single key from a provider's JWK Set (RFC 7517)
+#[derive(Debug, Deserialize)]
+struct JsonWebKey {
+    kid: String,
+    #[serde(default)]
+    n: String,
+    #[serde(default)]
+    e: String,
+}
+
+/// A provider's JWK Set, as published at its `jwks_uri`
+#[derive(Debug, Deserialize)]
+struct JwkSet {
+    keys: Vec<JsonWebKey>,
+}
+
+/// Validates an OIDC `id_token`'s signature and standard claims
+///
+/// Checks the RS256 signature against the issuer's JWKS, then `iss`, `aud`,
+/// `exp` and `nonce`, per the OpenID Connect Core 1.0 ID Token validation
+/// rules.
+struct IdTokenVerifier<'a> {
+    discovery: &'a OidcDiscoveryDocument,
+    client_id: &'a str,
+    nonce: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    #[serde(default)]
+    aud: serde_json::Value,
+    exp: u64,
+    #[serde(default)]
+    nonce: Option<String>,
+}
+
+impl<'a> IdTokenVerifier<'a> {
+    fn new(discovery: &'a OidcDiscoveryDocument, client_id: &'a str, nonce: &'a str) -> Self {
+        Self {
+            discovery,
+            client_id,
+            nonce,
+        }
+    }
+
+    /// Verify the signature and claims of `id_token`, returning the parsed claims on success
+    async fn verify(&self, id_token: &str) -> Result<IdTokenClaims, AuthError> {
+        let mut parts = id_token.split('.');
+        let header_b64 = parts
+            .next()
+            .ok_or_else(|| AuthError::IdTokenValidationFailed("Malformed id_token".to_string()))?;
+        let payload_b64 = parts
+            .next()
+            .ok_or_else(|| AuthError::IdTokenValidationFailed("Malformed id_token".to_string()))?;
+        let signature_b64 = parts
+            .next()
+            .ok_or_else(|| AuthError::IdTokenValidationFailed("Malformed id_token".to_string()))?;
+        if parts.next().is_some() {
+            return Err(AuthError::IdTokenValidationFailed("Malformed id_token".to_string()));
+        }
+
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let header_json = engine
+            .decode(header_b64)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid id_token header: {}", e)))?;
+        let header: serde_json::Value = serde_json::from_slice(&header_json)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid id_token header: {}", e)))?;
+        let kid = header
+            .get("kid")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AuthError::IdTokenValidationFailed("id_token header is missing kid".to_string()))?;
+
+        let payload_json = engine
+            .decode(payload_b64)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid id_token payload: {}", e)))?;
+        let claims: IdTokenClaims = serde_json::from_slice(&payload_json)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid id_token payload: {}", e)))?;
+
+        let signature = engine
+            .decode(signature_b64)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid id_token signature: {}", e)))?;
+
+        let jwk = self.fetch_signing_key(kid).await?;
+        self.verify_signature(&jwk, &format!("{}.{}", header_b64, payload_b64), &signature)?;
+
+        self.check_claims(&claims)?;
+
+        Ok(claims)
+    }
+
+    /// Fetch the provider's JWKS and select the key matching `kid`
+    async fn fetch_signing_key(&self, kid: &str) -> Result<JsonWebKey, AuthError> {
+        let jwks_uri = self
+            .discovery
+            .jwks_uri
+            .as_deref()
+            .ok_or_else(|| AuthError::IdTokenValidationFailed("Discovery document has no jwks_uri".to_string()))?;
+
+        let client = reqwest::Client::new();
+        let jwk_set: JwkSet = client
+            .get(jwks_uri)
+            .send()
+            .await
+            .map_err(|e| AuthError::NetworkError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Failed to parse JWKS: {}", e)))?;
+
+        jwk_set
+            .keys
+            .into_iter()
+            .find(|key| key.kid == kid)
+            .ok_or_else(|| AuthError::IdTokenValidationFailed(format!("No JWKS key found for kid {}", kid)))
+    }
+
+    /// Verify the RS256 signature over `signing_input` using the given JWK
+    fn verify_signature(&self, jwk: &JsonWebKey, signing_input: &str, signature: &[u8]) -> Result<(), AuthError> {
+        use rsa::pkcs1v15::{Signature, VerifyingKey};
+        use rsa::signature::Verifier;
+        use rsa::{BigUint, RsaPublicKey};
+
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let n = engine
+            .decode(&jwk.n)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid JWK modulus: {}", e)))?;
+        let e = engine
+            .decode(&jwk.e)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid JWK exponent: {}", e)))?;
+
+        let public_key = RsaPublicKey::new(BigUint::from_bytes_be(&n), BigUint::from_bytes_be(&e))
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid JWK key material: {}", e)))?;
+        let verifying_key = VerifyingKey::<Sha256>::new(public_key);
+        let signature = Signature::try_from(signature)
+            .map_err(|e| AuthError::IdTokenValidationFailed(format!("Invalid id_token signature: {}", e)))?;
+
+        verifying_key
+            .verify(signing_input.as_bytes(), &signature)
+            .map_err(|_| AuthError::IdTokenValidationFailed("id_token signature verification failed".to_string()))
+    }
+
+    /// Check `iss`, `aud`, `exp` and `nonce` against the expected values
+    fn check_claims(&self, claims: &IdTokenClaims) -> Result<(), AuthError> {
+        if claims.iss != self.discovery.issuer {
+            return Err(AuthError::IdTokenValidationFailed(format!(
+                "id_token iss {} does not match expected issuer {}",
+                claims.iss, self.discovery.issuer
+            )));
+        }
+
+        let audience_matches = match &claims.aud {
+            serde_json::Value::String(aud) => aud == self.client_id,
+            serde_json::Value::Array(auds) => auds.iter().any(|a| a.as_str() == Some(self.client_id)),
+            _ => false,
+        };
+        if !audience_matches {
+            return Err(AuthError::IdTokenValidationFailed(
+                "id_token aud does not contain our client_id".to_string(),
+            ));
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        if claims.exp <= now {
+            return Err(AuthError::IdTokenValidationFailed("id_token has expired".to_string()));
+        }
+
+        if claims.nonce.as_deref() != Some(self.nonce) {
+            return Err(AuthError::IdTokenValidationFailed(
+                "id_token nonce does not match the one sent in the authorization request".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Options controlling how [`OAuthFlow::execute_with_options`] obtains the
+/// authorization code and what it verifies afterward
+#[derive(Default)]
+pub struct ExecuteOptions<'a> {
+    /// Try to open the authorization URL in the system browser (only meaningful with `use_loopback`)
+    pub open_browser: bool,
+    /// Capture the authorization code via a loopback redirect instead of copy-paste
+    pub use_loopback: bool,
+    /// Validate the returned `id_token`'s nonce and JWKS signature against this discovery document
+    pub verify_id_token: Option<&'a OidcDiscoveryDocument>,
 }
 
 /// OAuth 2.0 Authorization Code Flow handler with PKCE (RFC 7636)
@@ -57,35 +426,299 @@ impl OAuthFlow {
 
     /// Execute the complete OAuth 2.0 Authorization Code Flow with copy-paste pattern
     pub async fn execute(&self) -> Result<OAuthTokens, AuthError> {
+        self.execute_with_options(ExecuteOptions::default()).await
+    }
+
+    /// Execute the Authorization Code Flow using a local loopback redirect
+    ///
+    /// Instead of asking the user to copy and paste the authorization code,
+    /// this binds a short-lived `TcpListener` on `127.0.0.1` and lets the
+    /// provider redirect the browser straight back to it. Falls back to
+    /// nothing automatically -- callers on headless machines should use
+    /// [`OAuthFlow::execute`] instead.
+    pub async fn execute_with_loopback(&self, open_browser: bool) -> Result<OAuthTokens, AuthError> {
+        self.execute_with_options(ExecuteOptions {
+            use_loopback: true,
+            open_browser,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Like [`Self::execute`], but also validates the returned `id_token`
+    /// against the given discovery document (nonce + JWKS signature)
+    pub async fn execute_with_id_token_verification(
+        &self,
+        discovery: &OidcDiscoveryDocument,
+    ) -> Result<OAuthTokens, AuthError> {
+        self.execute_with_options(ExecuteOptions {
+            verify_id_token: Some(discovery),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Run the Authorization Code Flow as configured by `options`
+    ///
+    /// This is the shared core behind [`Self::execute`], [`Self::execute_with_loopback`]
+    /// and [`Self::execute_with_id_token_verification`] -- those are thin presets over
+    /// this method, and callers that want both a loopback redirect and ID-token
+    /// verification at once can set both fields on [`ExecuteOptions`] directly rather
+    /// than needing a fourth method.
+    pub async fn execute_with_options(&self, options: ExecuteOptions<'_>) -> Result<OAuthTokens, AuthError> {
         let config = self.provider.config();
 
-        // Step 1: Generate PKCE code challenge and verifier
         let (code_challenge, code_verifier) = Self::generate_pkce_pair();
-
-        // Step 2: Generate state for CSRF protection
         let state = Self::generate_state();
+        let nonce = options.verify_id_token.is_some().then(Self::generate_state);
+
+        let mut extra_params: Vec<(&str, &str)> = Vec::new();
+        if let Some(nonce) = &nonce {
+            extra_params.push(("nonce", nonce));
+        }
+
+        let (redirect_uri, authorization_code) = if options.use_loopback {
+            let listener = Self::bind_loopback_listener(&config)?;
+            let port = listener
+                .local_addr()
+                .map_err(|e| AuthError::ConfigError(format!("Failed to read loopback address: {}", e)))?
+                .port();
+            let redirect_uri = format!("http://127.0.0.1:{}", port);
+            extra_params.push(("redirect_uri", &redirect_uri));
 
-        // Step 3: Build authorization URL
-        let auth_url = self.build_authorization_url(&config, &state, &code_challenge)?;
+            let auth_url =
+                self.build_authorization_url_with_params(&config, &state, &code_challenge, &extra_params)?;
+            if options.open_browser {
+                Self::open_in_browser(&auth_url);
+            }
+            println!("\n🔐 Please visit this URL to authorize Himalaya:\n");
+            println!("  {}\n", auth_url);
+            println!("Waiting for the authorization redirect...\n");
 
-        // Step 4: Display authorization URL to user
-        println!("\n🔐 Please visit this URL to authorize Himalaya:\n");
-        println!("  {}\n", auth_url);
-        println!("After authorizing, copy the authorization code from the page.\n");
+            // `await_redirect` blocks the calling thread on a polling accept loop, so it
+            // must run on a blocking-pool thread rather than the async task's own worker.
+            let timeout_secs = config.loopback_timeout_secs;
+            let redirect_state = state.clone();
+            let code = tokio::task::spawn_blocking(move || {
+                Self::await_redirect(listener, &redirect_state, timeout_secs)
+            })
+            .await
+            .map_err(|e| AuthError::ConfigError(format!("Loopback redirect task panicked: {}", e)))??;
 
-        // Step 5: Prompt user to paste authorization code
-        let authorization_code = self.prompt_for_authorization_code()?;
+            (Some(redirect_uri), code)
+        } else {
+            let auth_url =
+                self.build_authorization_url_with_params(&config, &state, &code_challenge, &extra_params)?;
+            println!("\n🔐 Please visit this URL to authorize Himalaya:\n");
+            println!("  {}\n", auth_url);
+            println!("After authorizing, copy the authorization code from the page.\n");
+
+            let code = self.prompt_for_authorization_code()?;
+            (None, code)
+        };
 
-        // Step 6: Exchange authorization code for tokens
         let tokens = self
-            .exchange_code_for_tokens(&config, &authorization_code, &code_verifier)
+            .exchange_code_for_tokens(&config, &authorization_code, &code_verifier, redirect_uri.as_deref())
             .await?;
 
+        if let Some(discovery) = options.verify_id_token {
+            let id_token = tokens.id_token.as_deref().ok_or_else(|| {
+                AuthError::IdTokenValidationFailed("Provider did not return an id_token".to_string())
+            })?;
+            let nonce = nonce.as_deref().expect("nonce is generated whenever verify_id_token is set");
+            IdTokenVerifier::new(discovery, &self.client_id, nonce)
+                .verify(id_token)
+                .await?;
+        }
+
         println!("✓ Authorization successful");
 
         Ok(tokens)
     }
 
+    /// Bind the loopback listener, honoring the provider's configured port range if present
+    fn bind_loopback_listener(config: &ProviderConfig) -> Result<std::net::TcpListener, AuthError> {
+        use std::net::TcpListener;
+
+        if let Some((start, end)) = config.loopback_port_range {
+            for port in start..=end {
+                if let Ok(listener) = TcpListener::bind(("127.0.0.1", port)) {
+                    return Ok(listener);
+                }
+            }
+            return Err(AuthError::ConfigError(format!(
+                "No free loopback port available in range {}-{}",
+                start, end
+            )));
+        }
+
+        TcpListener::bind(("127.0.0.1", 0))
+            .map_err(|e| AuthError::ConfigError(format!("Failed to bind loopback listener: {}", e)))
+    }
+
+    /// Block waiting for the redirect request, returning the authorization code
+    ///
+    /// Any connection that reaches the loopback port before the real browser
+    /// redirect (a stray local process, a port probe, a browser pre-connect)
+    /// is accepted and read, then discarded if it sends no request line within
+    /// `PER_CONNECTION_READ_TIMEOUT` or its request lacks a `code` or `state`.
+    /// The wait ends at the first request carrying both `code` and `state`
+    /// (with an error if that `state` doesn't match `expected_state`), at an
+    /// explicit `error=` from the provider, or once `timeout_secs` elapses.
+    fn await_redirect(
+        listener: std::net::TcpListener,
+        expected_state: &str,
+        timeout_secs: u64,
+    ) -> Result<String, AuthError> {
+        use std::io::BufRead;
+        use std::time::{Duration, Instant};
+
+        /// Read timeout for a single accepted connection, kept short and independent
+        /// of the overall deadline so a stray connection (port probe, browser
+        /// pre-connect) can't block the real redirect queued behind it.
+        const PER_CONNECTION_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| AuthError::ConfigError(format!("Failed to configure loopback listener: {}", e)))?;
+
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+
+        loop {
+            let mut stream = loop {
+                match listener.accept() {
+                    Ok((stream, _)) => break stream,
+                    Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        if Instant::now() >= deadline {
+                            return Err(AuthError::ConfigError(
+                                "Timed out waiting for the authorization redirect".to_string(),
+                            ));
+                        }
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => {
+                        return Err(AuthError::ConfigError(format!("Loopback accept failed: {}", e)));
+                    }
+                }
+            };
+            stream
+                .set_nonblocking(false)
+                .map_err(|e| AuthError::ConfigError(format!("Failed to configure redirect connection: {}", e)))?;
+
+            stream
+                .set_read_timeout(Some(PER_CONNECTION_READ_TIMEOUT))
+                .map_err(|e| AuthError::ConfigError(format!("Failed to configure redirect connection: {}", e)))?;
+
+            let mut reader = std::io::BufReader::new(&stream);
+            let mut request_line = String::new();
+            match reader.read_line(&mut request_line) {
+                Ok(0) => continue,
+                Ok(_) => {}
+                // This connection didn't send a full request line within its own short
+                // timeout; it isn't the redirect we're waiting for. Keep accepting until
+                // the overall deadline (checked in the accept loop above) is reached.
+                Err(ref e)
+                    if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+                {
+                    continue;
+                }
+                Err(_) => continue,
+            }
+
+            let (code, state) = match Self::parse_redirect_query(&request_line) {
+                Ok(parsed) => parsed,
+                // A genuine authorization error from the provider is terminal; anything else
+                // (a stray connection, a port probe) just isn't the redirect we're waiting for.
+                Err(AuthError::ConfigError(msg)) if msg.starts_with("Authorization server returned an error") => {
+                    return Err(AuthError::ConfigError(msg));
+                }
+                Err(_) => continue,
+            };
+
+            if state != expected_state {
+                let _ = Self::write_redirect_response(&mut stream, false);
+                return Err(AuthError::ConfigError(
+                    "Authorization state mismatch (possible CSRF attempt)".to_string(),
+                ));
+            }
+
+            Self::write_redirect_response(&mut stream, true)
+                .map_err(|e| AuthError::ConfigError(format!("Failed to write redirect response: {}", e)))?;
+
+            return Ok(code);
+        }
+    }
+
+    /// Parse the `code` and `state` query parameters out of an HTTP request line
+    fn parse_redirect_query(request_line: &str) -> Result<(String, String), AuthError> {
+        let path = request_line
+            .split_whitespace()
+            .nth(1)
+            .ok_or_else(|| AuthError::ConfigError("Malformed redirect request".to_string()))?;
+
+        let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
+
+        let mut code = None;
+        let mut state = None;
+        for pair in query.split('&') {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or("");
+            let value = parts.next().unwrap_or("");
+            let value = urlencoding::decode(value)
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| value.to_string());
+            match key {
+                "code" => code = Some(value),
+                "state" => state = Some(value),
+                "error" => {
+                    return Err(AuthError::ConfigError(format!(
+                        "Authorization server returned an error: {}",
+                        value
+                    )))
+                }
+                _ => {}
+            }
+        }
+
+        let code = code.ok_or_else(|| AuthError::ConfigError("Redirect did not include a code".to_string()))?;
+        let state = state.ok_or_else(|| AuthError::ConfigError("Redirect did not include a state".to_string()))?;
+
+        Ok((code, state))
+    }
+
+    /// Write the "you may close this tab" response back to the browser
+    fn write_redirect_response(stream: &mut std::net::TcpStream, success: bool) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let body = if success {
+            "<html><body><h3>Authorization complete</h3><p>You may close this tab and return to Himalaya.</p></body></html>"
+        } else {
+            "<html><body><h3>Authorization failed</h3><p>State mismatch, please try again.</p></body></html>"
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes())?;
+        stream.flush()
+    }
+
+    /// Best-effort attempt to open the authorization URL in the system browser
+    fn open_in_browser(url: &str) {
+        let result = if cfg!(target_os = "macos") {
+            std::process::Command::new("open").arg(url).status()
+        } else if cfg!(target_os = "windows") {
+            std::process::Command::new("cmd").args(["/C", "start", "", url]).status()
+        } else {
+            std::process::Command::new("xdg-open").arg(url).status()
+        };
+
+        if let Err(e) = result {
+            println!("Could not open browser automatically ({}); please open the URL manually.", e);
+        }
+    }
+
     /// Generate PKCE code challenge and verifier
     fn generate_pkce_pair() -> (String, String) {
         use rand::Rng;
@@ -121,12 +754,14 @@ impl OAuthFlow {
             .collect()
     }
 
-    /// Build the authorization URL
-    fn build_authorization_url(
+    /// Build the authorization URL, optionally appending extra query parameters
+    /// (e.g. `redirect_uri` for the loopback flow, `nonce` for ID-token verification)
+    fn build_authorization_url_with_params(
         &self,
         config: &ProviderConfig,
         state: &str,
         code_challenge: &str,
+        extra: &[(&str, &str)],
     ) -> Result<String, AuthError> {
         let params = [
             ("client_id", self.client_id.as_str()),
@@ -140,6 +775,7 @@ impl OAuthFlow {
 
         let query = params
             .iter()
+            .chain(extra.iter())
             .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
             .collect::<Vec<_>>()
             .join("&");
@@ -179,22 +815,24 @@ impl OAuthFlow {
         config: &ProviderConfig,
         code: &str,
         code_verifier: &str,
+        redirect_uri: Option<&str>,
     ) -> Result<OAuthTokens, AuthError> {
         let client = reqwest::Client::new();
 
+        let (client_id, client_secret) = body_credentials(&self.client_id, &self.client_secret, config.client_auth_method);
         let request = TokenRequest {
-            client_id: self.client_id.clone(),
-            client_secret: self.client_secret.clone(),
+            client_id,
+            client_secret,
             code: code.to_string(),
             grant_type: "authorization_code".to_string(),
             code_verifier: code_verifier.to_string(),
+            redirect_uri: redirect_uri.map(|s| s.to_string()),
         };
 
         println!("🔄 Exchanging authorization code for tokens...");
 
-        let response = client
-            .post(config.token_url)
-            .json(&request)
+        let builder = apply_client_auth(&self.client_id, &self.client_secret, config.client_auth_method, client.post(config.token_url.clone()));
+        let response = encode_token_request(config, builder, &request)
             .send()
             .await
             .map_err(|e| AuthError::NetworkError(e.to_string()))?;
@@ -224,8 +862,369 @@ impl OAuthFlow {
             access_token: token_response.access_token,
             refresh_token: token_response.refresh_token,
             expires_in: token_response.expires_in,
+            id_token: token_response.id_token,
         })
     }
+
+    /// Renew an expired access token using the refresh-token grant
+    ///
+    /// Many providers (e.g. Google) omit `refresh_token` from the refresh
+    /// response, so the caller's existing refresh token is preserved when
+    /// the server doesn't send a new one.
+    pub async fn refresh_tokens(&self, refresh_token: &str) -> Result<OAuthTokens, AuthError> {
+        let config = self.provider.config();
+        let client = reqwest::Client::new();
+
+        let (client_id, client_secret) = body_credentials(&self.client_id, &self.client_secret, config.client_auth_method);
+        let request = RefreshTokenRequest {
+            client_id,
+            client_secret,
+            refresh_token: refresh_token.to_string(),
+            grant_type: "refresh_token".to_string(),
+        };
+
+        let builder = apply_client_auth(&self.client_id, &self.client_secret, config.client_auth_method, client.post(config.token_url.clone()));
+        let response = encode_token_request(&config, builder, &request)
+            .send()
+            .await
+            .map_err(|e| AuthError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AuthError::TokenExchangeFailed(format!(
+                "Failed to refresh access token: {}",
+                error_text
+            )));
+        }
+
+        let token_response: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| AuthError::TokenExchangeFailed(format!(
+                "Failed to parse refresh response: {}",
+                e
+            )))?;
+
+        Ok(OAuthTokens {
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token.or_else(|| Some(refresh_token.to_string())),
+            expires_in: token_response.expires_in,
+            id_token: token_response.id_token,
+        })
+    }
+
+    /// Revoke a token server-side so it can no longer be used (RFC 7009)
+    ///
+    /// Providers that don't support OIDC discovery must configure
+    /// `revocation_endpoint` on [`ProviderConfig`] directly.
+    pub async fn revoke_token(&self, token: &str, kind: TokenKind) -> Result<(), AuthError> {
+        let config = self.provider.config();
+        let revocation_endpoint = config
+            .revocation_endpoint
+            .clone()
+            .ok_or_else(|| AuthError::ConfigError("Provider has no revocation_endpoint configured".to_string()))?;
+
+        let client = reqwest::Client::new();
+        let (client_id, client_secret) = body_credentials(&self.client_id, &self.client_secret, config.client_auth_method);
+        let request = RevocationRequest {
+            client_id,
+            client_secret,
+            token: token.to_string(),
+            token_type_hint: kind.as_str().to_string(),
+        };
+
+        let builder = apply_client_auth(&self.client_id, &self.client_secret, config.client_auth_method, client.post(revocation_endpoint));
+        let response = encode_token_request(&config, builder, &request)
+            .send()
+            .await
+            .map_err(|e| AuthError::NetworkError(e.to_string()))?;
+
+        if response.status().is_success() {
+            return Ok(());
+        }
+
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        Err(AuthError::TokenExchangeFailed(format!(
+            "Failed to revoke token: {}",
+            error_text
+        )))
+    }
+}
+
+/// Which kind of token is being revoked, per the `token_type_hint` in RFC 7009
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    AccessToken,
+    RefreshToken,
+}
+
+impl TokenKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            TokenKind::AccessToken => "access_token",
+            TokenKind::RefreshToken => "refresh_token",
+        }
+    }
+}
+
+/// Request body for RFC 7009 token revocation
+#[derive(Debug, Serialize)]
+struct RevocationRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    token: String,
+    token_type_hint: String,
+}
+
+/// Request to start a device authorization grant (RFC 8628 §3.1)
+///
+/// Confidential clients authenticate the same way as the other grants in
+/// this module, so `client_id`/`client_secret` follow the same
+/// [`ClientAuthMethod`]-driven rules as [`TokenRequest`].
+#[derive(Debug, Serialize)]
+struct DeviceAuthorizationRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    scope: String,
+}
+
+/// Response from the device authorization endpoint (RFC 8628 §3.2)
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    #[serde(default = "default_device_poll_interval")]
+    interval: u64,
+}
+
+fn default_device_poll_interval() -> u64 {
+    5
+}
+
+/// Token request for the device-code grant (RFC 8628 §3.4)
+#[derive(Debug, Serialize)]
+struct DeviceTokenRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    device_code: String,
+    grant_type: String,
+}
+
+/// Error body returned while polling the token endpoint (RFC 8628 §3.5)
+#[derive(Debug, Deserialize)]
+struct DeviceTokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// What a device-code poll should do next, given the `error` field of a
+/// non-2xx response from the token endpoint (RFC 8628 §3.5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DevicePollAction {
+    /// `authorization_pending`: the user hasn't finished authorizing yet, keep polling
+    KeepPolling,
+    /// `slow_down`: keep polling, but back off the interval by 5 seconds
+    SlowDown,
+    /// `access_denied`: the user rejected the request, stop polling
+    AccessDenied,
+    /// `expired_token`: the device code expired before authorization completed
+    Expired,
+    /// Any other error code the provider returned, treated as a terminal failure
+    Other,
+}
+
+/// Classify a device poll error code into the action `poll_for_tokens` should take
+fn classify_device_poll_error(error: &str) -> DevicePollAction {
+    match error {
+        "authorization_pending" => DevicePollAction::KeepPolling,
+        "slow_down" => DevicePollAction::SlowDown,
+        "access_denied" => DevicePollAction::AccessDenied,
+        "expired_token" => DevicePollAction::Expired,
+        _ => DevicePollAction::Other,
+    }
+}
+
+
+/// RFC 8628 Device Authorization Grant handler
+///
+/// Unlike [`OAuthFlow`], this never needs a browser or redirect on the
+/// machine running Himalaya: the user authorizes on any other device,
+/// while this process polls the token endpoint until they do.
+pub struct DeviceFlow {
+    provider: AuthProvider,
+    client_id: String,
+    client_secret: String,
+}
+
+impl DeviceFlow {
+    /// Create a new device flow
+    pub fn new(provider: AuthProvider, client_id: String, client_secret: String) -> Self {
+        Self {
+            provider,
+            client_id,
+            client_secret,
+        }
+    }
+
+    /// Run the full device grant: request a device code, prompt the user, then poll for tokens
+    pub async fn execute(&self) -> Result<OAuthTokens, AuthError> {
+        let config = self.provider.config();
+        let client = reqwest::Client::new();
+
+        let authorization = self.request_device_authorization(&client, &config).await?;
+
+        println!("\n🔐 To authorize Himalaya, visit:\n");
+        println!("  {}\n", authorization.verification_uri);
+        println!("And enter this code: {}\n", authorization.user_code);
+        if let Some(complete_uri) = &authorization.verification_uri_complete {
+            println!("Or open this link directly: {}\n", complete_uri);
+        }
+        println!("Waiting for authorization...\n");
+
+        self.poll_for_tokens(&client, &config, &authorization).await
+    }
+
+    /// Request a device code and user code from the provider
+    async fn request_device_authorization(
+        &self,
+        client: &reqwest::Client,
+        config: &ProviderConfig,
+    ) -> Result<DeviceAuthorizationResponse, AuthError> {
+        let device_authorization_url = config.device_authorization_url.clone();
+
+        let (client_id, client_secret) = body_credentials(&self.client_id, &self.client_secret, config.client_auth_method);
+        let request = DeviceAuthorizationRequest {
+            client_id,
+            client_secret,
+            scope: config.scopes_str(),
+        };
+
+        let builder = apply_client_auth(
+            &self.client_id,
+            &self.client_secret,
+            config.client_auth_method,
+            client.post(device_authorization_url),
+        );
+        let response = encode_token_request(config, builder, &request)
+            .send()
+            .await
+            .map_err(|e| AuthError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AuthError::TokenExchangeFailed(format!(
+                "Failed to start device authorization: {}",
+                error_text
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| AuthError::TokenExchangeFailed(format!(
+                "Failed to parse device authorization response: {}",
+                e
+            )))
+    }
+
+    /// Poll the token endpoint until the user authorizes, the device code expires, or
+    /// the provider rejects the request
+    async fn poll_for_tokens(
+        &self,
+        client: &reqwest::Client,
+        config: &ProviderConfig,
+        authorization: &DeviceAuthorizationResponse,
+    ) -> Result<OAuthTokens, AuthError> {
+        let mut interval = std::time::Duration::from_secs(authorization.interval);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(authorization.expires_in);
+
+        let (client_id, client_secret) = body_credentials(&self.client_id, &self.client_secret, config.client_auth_method);
+        let request = DeviceTokenRequest {
+            client_id,
+            client_secret,
+            device_code: authorization.device_code.clone(),
+            grant_type: "urn:ietf:params:oauth:grant-type:device_code".to_string(),
+        };
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if std::time::Instant::now() >= deadline {
+                return Err(AuthError::TokenExchangeFailed(
+                    "Device code expired before authorization completed".to_string(),
+                ));
+            }
+
+            let builder = apply_client_auth(&self.client_id, &self.client_secret, config.client_auth_method, client.post(config.token_url.clone()));
+            let response = encode_token_request(config, builder, &request)
+                .send()
+                .await
+                .map_err(|e| AuthError::NetworkError(e.to_string()))?;
+
+            if response.status().is_success() {
+                let token_response: TokenResponse = response.json().await.map_err(|e| {
+                    AuthError::TokenExchangeFailed(format!("Failed to parse token response: {}", e))
+                })?;
+
+                println!("✓ Authorization successful");
+
+                return Ok(OAuthTokens {
+                    access_token: token_response.access_token,
+                    refresh_token: token_response.refresh_token,
+                    expires_in: token_response.expires_in,
+                    id_token: token_response.id_token,
+                });
+            }
+
+            let error_body: DeviceTokenErrorResponse = response.json().await.map_err(|e| {
+                AuthError::TokenExchangeFailed(format!("Failed to parse device poll error: {}", e))
+            })?;
+
+            match classify_device_poll_error(&error_body.error) {
+                DevicePollAction::KeepPolling => continue,
+                DevicePollAction::SlowDown => {
+                    interval += std::time::Duration::from_secs(5);
+                }
+                DevicePollAction::AccessDenied => {
+                    return Err(AuthError::TokenExchangeFailed(
+                        "User denied the authorization request".to_string(),
+                    ));
+                }
+                DevicePollAction::Expired => {
+                    return Err(AuthError::TokenExchangeFailed(
+                        "Device code expired before authorization completed".to_string(),
+                    ));
+                }
+                DevicePollAction::Other => {
+                    return Err(AuthError::TokenExchangeFailed(format!(
+                        "Device authorization failed: {} ({})",
+                        error_body.error,
+                        error_body.error_description.unwrap_or_default()
+                    )));
+                }
+            }
+        }
+    }
 }
 
 /// Characters allowed in PKCE code verifier (RFC 7636)
@@ -241,6 +1240,7 @@ mod tests {
             access_token: "test_token".to_string(),
             refresh_token: Some("refresh_token".to_string()),
             expires_in: Some(3600),
+            id_token: None,
         };
 
         assert_eq!(tokens.access_token, "test_token");
@@ -264,6 +1264,29 @@ mod tests {
         assert!(!challenge.contains("="));
     }
 
+    #[test]
+    fn test_is_expired() {
+        use std::time::{Duration, SystemTime};
+
+        let tokens = OAuthTokens {
+            access_token: "test_token".to_string(),
+            refresh_token: None,
+            expires_in: Some(3600),
+            id_token: None,
+        };
+
+        assert!(!tokens.is_expired(SystemTime::now()));
+        assert!(tokens.is_expired(SystemTime::now() - Duration::from_secs(3600)));
+
+        let never_expiring = OAuthTokens {
+            access_token: "test_token".to_string(),
+            refresh_token: None,
+            expires_in: None,
+            id_token: None,
+        };
+        assert!(!never_expiring.is_expired(SystemTime::now() - Duration::from_secs(1_000_000)));
+    }
+
     #[test]
     fn test_state_generation() {
         let state = OAuthFlow::generate_state();
@@ -276,4 +1299,400 @@ mod tests {
             assert!(PKCE_CHARSET.contains(&(c as u8)));
         }
     }
+
+    #[test]
+    fn test_parse_redirect_query_extracts_code_and_state() {
+        let (code, state) =
+            OAuthFlow::parse_redirect_query("GET /callback?code=abc123&state=xyz789 HTTP/1.1").unwrap();
+
+        assert_eq!(code, "abc123");
+        assert_eq!(state, "xyz789");
+    }
+
+    #[test]
+    fn test_parse_redirect_query_url_decodes_values() {
+        let (code, state) =
+            OAuthFlow::parse_redirect_query("GET /callback?code=foo%2Fbar&state=a%20b HTTP/1.1").unwrap();
+
+        assert_eq!(code, "foo/bar");
+        assert_eq!(state, "a b");
+    }
+
+    #[test]
+    fn test_parse_redirect_query_rejects_provider_error() {
+        let result = OAuthFlow::parse_redirect_query("GET /callback?error=access_denied&state=xyz HTTP/1.1");
+
+        assert!(matches!(result, Err(AuthError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_parse_redirect_query_rejects_missing_code() {
+        let result = OAuthFlow::parse_redirect_query("GET /callback?state=xyz789 HTTP/1.1");
+
+        assert!(matches!(result, Err(AuthError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_parse_redirect_query_rejects_missing_state() {
+        let result = OAuthFlow::parse_redirect_query("GET /callback?code=abc123 HTTP/1.1");
+
+        assert!(matches!(result, Err(AuthError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_parse_redirect_query_rejects_malformed_request_line() {
+        let result = OAuthFlow::parse_redirect_query("");
+
+        assert!(matches!(result, Err(AuthError::ConfigError(_))));
+    }
+
+    fn test_discovery_document() -> OidcDiscoveryDocument {
+        OidcDiscoveryDocument {
+            issuer: "https://idp.example.com".to_string(),
+            authorization_endpoint: "https://idp.example.com/authorize".to_string(),
+            token_endpoint: "https://idp.example.com/token".to_string(),
+            revocation_endpoint: None,
+            jwks_uri: Some("https://idp.example.com/jwks".to_string()),
+            scopes_supported: vec![],
+            code_challenge_methods_supported: Some(vec!["S256".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_supports_pkce_s256_true_when_unspecified() {
+        let mut discovery = test_discovery_document();
+        discovery.code_challenge_methods_supported = None;
+
+        assert!(discovery.supports_pkce_s256());
+    }
+
+    #[test]
+    fn test_supports_pkce_s256_true_when_s256_advertised() {
+        let mut discovery = test_discovery_document();
+        discovery.code_challenge_methods_supported = Some(vec!["S256".to_string()]);
+
+        assert!(discovery.supports_pkce_s256());
+    }
+
+    #[test]
+    fn test_supports_pkce_s256_false_when_only_plain_advertised() {
+        let mut discovery = test_discovery_document();
+        discovery.code_challenge_methods_supported = Some(vec!["plain".to_string()]);
+
+        assert!(!discovery.supports_pkce_s256());
+    }
+
+    #[test]
+    fn test_supports_pkce_s256_false_when_list_empty() {
+        let mut discovery = test_discovery_document();
+        discovery.code_challenge_methods_supported = Some(vec![]);
+
+        assert!(!discovery.supports_pkce_s256());
+    }
+
+    #[test]
+    fn test_into_provider_config_succeeds_when_s256_unspecified() {
+        let mut discovery = test_discovery_document();
+        discovery.code_challenge_methods_supported = None;
+
+        assert!(discovery.into_provider_config().is_ok());
+    }
+
+    #[test]
+    fn test_into_provider_config_rejects_explicit_non_s256_support() {
+        let mut discovery = test_discovery_document();
+        discovery.code_challenge_methods_supported = Some(vec!["plain".to_string()]);
+
+        assert!(matches!(discovery.into_provider_config(), Err(AuthError::ConfigError(_))));
+    }
+
+    fn valid_claims(discovery: &OidcDiscoveryDocument, client_id: &str, nonce: &str) -> IdTokenClaims {
+        IdTokenClaims {
+            iss: discovery.issuer.clone(),
+            aud: serde_json::Value::String(client_id.to_string()),
+            exp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                + 3600,
+            nonce: Some(nonce.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_check_claims_accepts_matching_claims() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let claims = valid_claims(&discovery, "my-client-id", "my-nonce");
+
+        assert!(verifier.check_claims(&claims).is_ok());
+    }
+
+    #[test]
+    fn test_check_claims_rejects_issuer_mismatch() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let mut claims = valid_claims(&discovery, "my-client-id", "my-nonce");
+        claims.iss = "https://evil.example.com".to_string();
+
+        assert!(matches!(verifier.check_claims(&claims), Err(AuthError::IdTokenValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_check_claims_rejects_audience_mismatch() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let claims = valid_claims(&discovery, "someone-else", "my-nonce");
+
+        assert!(matches!(verifier.check_claims(&claims), Err(AuthError::IdTokenValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_check_claims_accepts_audience_array_containing_client_id() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let mut claims = valid_claims(&discovery, "my-client-id", "my-nonce");
+        claims.aud = serde_json::Value::Array(vec![
+            serde_json::Value::String("other-client".to_string()),
+            serde_json::Value::String("my-client-id".to_string()),
+        ]);
+
+        assert!(verifier.check_claims(&claims).is_ok());
+    }
+
+    #[test]
+    fn test_check_claims_rejects_expired_token() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let mut claims = valid_claims(&discovery, "my-client-id", "my-nonce");
+        claims.exp = 1;
+
+        assert!(matches!(verifier.check_claims(&claims), Err(AuthError::IdTokenValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_check_claims_rejects_nonce_mismatch() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let mut claims = valid_claims(&discovery, "my-client-id", "my-nonce");
+        claims.nonce = Some("wrong-nonce".to_string());
+
+        assert!(matches!(verifier.check_claims(&claims), Err(AuthError::IdTokenValidationFailed(_))));
+    }
+
+    /// Generates an RSA keypair and a JWK whose `n`/`e` describe its public half,
+    /// so tests can sign with the private key and verify against the JWK alone
+    fn test_rsa_keypair_and_jwk(kid: &str) -> (rsa::RsaPrivateKey, JsonWebKey) {
+        use rsa::traits::PublicKeyParts;
+
+        let private_key = rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 2048)
+            .expect("failed to generate RSA key for test");
+        let public_key = private_key.to_public_key();
+
+        let engine = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        let jwk = JsonWebKey {
+            kid: kid.to_string(),
+            n: engine.encode(public_key.n().to_bytes_be()),
+            e: engine.encode(public_key.e().to_bytes_be()),
+        };
+
+        (private_key, jwk)
+    }
+
+    /// Signs `signing_input` with `private_key` using RS256 (PKCS#1 v1.5 + SHA-256)
+    fn sign_rs256(private_key: &rsa::RsaPrivateKey, signing_input: &str) -> Vec<u8> {
+        use rsa::pkcs1v15::SigningKey;
+        use rsa::signature::{SignatureEncoding, Signer};
+
+        let signing_key = SigningKey::<Sha256>::new(private_key.clone());
+        signing_key.sign(signing_input.as_bytes()).to_vec()
+    }
+
+    #[test]
+    fn test_verify_signature_accepts_valid_rs256_signature() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let (private_key, jwk) = test_rsa_keypair_and_jwk("test-kid");
+
+        let signing_input = "header.payload";
+        let signature = sign_rs256(&private_key, signing_input);
+
+        assert!(verifier.verify_signature(&jwk, signing_input, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_tampered_signature() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let (private_key, jwk) = test_rsa_keypair_and_jwk("test-kid");
+
+        let signing_input = "header.payload";
+        let mut signature = sign_rs256(&private_key, signing_input);
+        let last = signature.len() - 1;
+        signature[last] ^= 0xff;
+
+        assert!(matches!(
+            verifier.verify_signature(&jwk, signing_input, &signature),
+            Err(AuthError::IdTokenValidationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_signature_from_different_key() {
+        let discovery = test_discovery_document();
+        let verifier = IdTokenVerifier::new(&discovery, "my-client-id", "my-nonce");
+        let (_signing_key, jwk) = test_rsa_keypair_and_jwk("test-kid");
+        let (other_key, _other_jwk) = test_rsa_keypair_and_jwk("other-kid");
+
+        let signing_input = "header.payload";
+        let signature = sign_rs256(&other_key, signing_input);
+
+        assert!(matches!(
+            verifier.verify_signature(&jwk, signing_input, &signature),
+            Err(AuthError::IdTokenValidationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_body_credentials_client_secret_post_includes_both() {
+        let (client_id, client_secret) = body_credentials("abc", "shh", ClientAuthMethod::ClientSecretPost);
+
+        assert_eq!(client_id, Some("abc".to_string()));
+        assert_eq!(client_secret, Some("shh".to_string()));
+    }
+
+    #[test]
+    fn test_body_credentials_client_secret_basic_omits_both() {
+        let (client_id, client_secret) = body_credentials("abc", "shh", ClientAuthMethod::ClientSecretBasic);
+
+        assert_eq!(client_id, None);
+        assert_eq!(client_secret, None);
+    }
+
+    #[test]
+    fn test_body_credentials_none_includes_only_client_id() {
+        let (client_id, client_secret) = body_credentials("abc", "shh", ClientAuthMethod::None);
+
+        assert_eq!(client_id, Some("abc".to_string()));
+        assert_eq!(client_secret, None);
+    }
+
+    #[test]
+    fn test_apply_client_auth_basic_sets_authorization_header() {
+        let client = reqwest::Client::new();
+        let builder = apply_client_auth(
+            "abc",
+            "shh",
+            ClientAuthMethod::ClientSecretBasic,
+            client.get("https://example.com"),
+        );
+        let request = builder.build().unwrap();
+
+        assert!(request.headers().contains_key(reqwest::header::AUTHORIZATION));
+    }
+
+    #[test]
+    fn test_apply_client_auth_post_leaves_request_unchanged() {
+        let client = reqwest::Client::new();
+        let builder = apply_client_auth(
+            "abc",
+            "shh",
+            ClientAuthMethod::ClientSecretPost,
+            client.get("https://example.com"),
+        );
+        let request = builder.build().unwrap();
+
+        assert!(!request.headers().contains_key(reqwest::header::AUTHORIZATION));
+    }
+
+    #[test]
+    fn test_apply_client_auth_none_leaves_request_unchanged() {
+        let client = reqwest::Client::new();
+        let builder = apply_client_auth(
+            "abc",
+            "shh",
+            ClientAuthMethod::None,
+            client.get("https://example.com"),
+        );
+        let request = builder.build().unwrap();
+
+        assert!(!request.headers().contains_key(reqwest::header::AUTHORIZATION));
+    }
+
+    /// Minimal [`ProviderConfig`] fixture for tests that only care about the
+    /// token-request encoding, not any particular provider's endpoints
+    fn test_provider_config(use_json_token_requests: bool) -> ProviderConfig {
+        ProviderConfig {
+            auth_url: "https://idp.example.com/authorize".to_string(),
+            token_url: "https://idp.example.com/token".to_string(),
+            device_authorization_url: "https://idp.example.com/device/code".to_string(),
+            revocation_endpoint: None,
+            jwks_uri: None,
+            issuer: None,
+            loopback_port_range: None,
+            loopback_timeout_secs: 120,
+            client_auth_method: ClientAuthMethod::ClientSecretPost,
+            scopes: vec!["email".to_string()],
+            use_json_token_requests,
+        }
+    }
+
+    fn test_refresh_token_request() -> RefreshTokenRequest {
+        RefreshTokenRequest {
+            client_id: Some("abc".to_string()),
+            client_secret: Some("shh".to_string()),
+            refresh_token: "rt".to_string(),
+            grant_type: "refresh_token".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_encode_token_request_defaults_to_form_encoded() {
+        let config = test_provider_config(false);
+        let client = reqwest::Client::new();
+        let request = encode_token_request(&config, client.post("https://example.com"), &test_refresh_token_request())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.headers().get(reqwest::header::CONTENT_TYPE).unwrap(),
+            "application/x-www-form-urlencoded"
+        );
+    }
+
+    #[test]
+    fn test_encode_token_request_opts_into_json() {
+        let config = test_provider_config(true);
+        let client = reqwest::Client::new();
+        let request = encode_token_request(&config, client.post("https://example.com"), &test_refresh_token_request())
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers().get(reqwest::header::CONTENT_TYPE).unwrap(), "application/json");
+    }
+
+    #[test]
+    fn test_classify_device_poll_error_authorization_pending_keeps_polling() {
+        assert_eq!(classify_device_poll_error("authorization_pending"), DevicePollAction::KeepPolling);
+    }
+
+    #[test]
+    fn test_classify_device_poll_error_slow_down_backs_off() {
+        assert_eq!(classify_device_poll_error("slow_down"), DevicePollAction::SlowDown);
+    }
+
+    #[test]
+    fn test_classify_device_poll_error_access_denied_is_terminal() {
+        assert_eq!(classify_device_poll_error("access_denied"), DevicePollAction::AccessDenied);
+    }
+
+    #[test]
+    fn test_classify_device_poll_error_expired_token_is_terminal() {
+        assert_eq!(classify_device_poll_error("expired_token"), DevicePollAction::Expired);
+    }
+
+    #[test]
+    fn test_classify_device_poll_error_unknown_code_is_terminal() {
+        assert_eq!(classify_device_poll_error("some_unexpected_error"), DevicePollAction::Other);
+    }
 }
@@ -0,0 +1,121 @@
+use super::flow::ClientAuthMethod;
+
+/// Email providers Himalaya knows how to authenticate against out of the box
+///
+/// `Custom` covers any provider configured by issuer URL via
+/// [`super::flow::OidcDiscoveryDocument::into_provider_config`] instead of
+/// one of the hand-maintained endpoint tables below.
+#[derive(Debug, Clone)]
+pub enum AuthProvider {
+    Google,
+    Microsoft,
+    Custom(ProviderConfig),
+}
+
+impl AuthProvider {
+    /// Resolve this provider to the [`ProviderConfig`] needed to run a flow against it
+    pub fn config(&self) -> ProviderConfig {
+        match self {
+            AuthProvider::Google => ProviderConfig::google(),
+            AuthProvider::Microsoft => ProviderConfig::microsoft(),
+            AuthProvider::Custom(config) => config.clone(),
+        }
+    }
+}
+
+/// Endpoints and settings needed to run an OAuth 2.0 / OIDC flow against a given provider
+#[derive(Debug, Clone)]
+pub struct ProviderConfig {
+    pub auth_url: String,
+    pub token_url: String,
+    pub device_authorization_url: String,
+    pub revocation_endpoint: Option<String>,
+    pub jwks_uri: Option<String>,
+    /// The issuer this config was built from, if any (set for OIDC-discovered
+    /// providers, unset for the hand-maintained tables below)
+    pub issuer: Option<String>,
+    /// `(start, end)` port range the loopback redirect listener may bind to;
+    /// `None` lets the OS pick an ephemeral port
+    pub loopback_port_range: Option<(u16, u16)>,
+    pub loopback_timeout_secs: u64,
+    pub client_auth_method: ClientAuthMethod,
+    pub scopes: Vec<String>,
+    pub use_json_token_requests: bool,
+}
+
+impl ProviderConfig {
+    const DEFAULT_LOOPBACK_TIMEOUT_SECS: u64 = 120;
+
+    fn google() -> Self {
+        ProviderConfig {
+            auth_url: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            token_url: "https://oauth2.googleapis.com/token".to_string(),
+            device_authorization_url: "https://oauth2.googleapis.com/device/code".to_string(),
+            revocation_endpoint: Some("https://oauth2.googleapis.com/revoke".to_string()),
+            jwks_uri: Some("https://www.googleapis.com/oauth2/v3/certs".to_string()),
+            issuer: None,
+            loopback_port_range: None,
+            loopback_timeout_secs: Self::DEFAULT_LOOPBACK_TIMEOUT_SECS,
+            client_auth_method: ClientAuthMethod::ClientSecretPost,
+            scopes: vec![
+                "https://mail.google.com/".to_string(),
+                "https://www.googleapis.com/auth/userinfo.email".to_string(),
+            ],
+            use_json_token_requests: false,
+        }
+    }
+
+    fn microsoft() -> Self {
+        ProviderConfig {
+            auth_url: "https://login.microsoftonline.com/common/oauth2/v2.0/authorize".to_string(),
+            token_url: "https://login.microsoftonline.com/common/oauth2/v2.0/token".to_string(),
+            device_authorization_url: "https://login.microsoftonline.com/common/oauth2/v2.0/devicecode".to_string(),
+            revocation_endpoint: None,
+            jwks_uri: Some("https://login.microsoftonline.com/common/discovery/v2.0/keys".to_string()),
+            issuer: None,
+            loopback_port_range: None,
+            loopback_timeout_secs: Self::DEFAULT_LOOPBACK_TIMEOUT_SECS,
+            client_auth_method: ClientAuthMethod::ClientSecretPost,
+            scopes: vec![
+                "https://outlook.office.com/IMAP.AccessAsUser.All".to_string(),
+                "offline_access".to_string(),
+            ],
+            use_json_token_requests: false,
+        }
+    }
+
+    /// Build a [`ProviderConfig`] from an OIDC discovery document's metadata
+    ///
+    /// OIDC Discovery 1.0 has no standard field for a device-authorization
+    /// endpoint (that's an RFC 8628 extension some providers add under their
+    /// own key), so `device_authorization_url` is left empty here; callers
+    /// that need [`super::flow::DeviceFlow`] against a discovered issuer must
+    /// set it on the returned config themselves.
+    pub fn from_oidc_discovery(
+        authorization_endpoint: String,
+        token_endpoint: String,
+        revocation_endpoint: Option<String>,
+        jwks_uri: Option<String>,
+        issuer: String,
+        scopes_supported: Vec<String>,
+    ) -> Self {
+        ProviderConfig {
+            auth_url: authorization_endpoint,
+            token_url: token_endpoint,
+            device_authorization_url: String::new(),
+            revocation_endpoint,
+            jwks_uri,
+            issuer: Some(issuer),
+            loopback_port_range: None,
+            loopback_timeout_secs: Self::DEFAULT_LOOPBACK_TIMEOUT_SECS,
+            client_auth_method: ClientAuthMethod::ClientSecretPost,
+            scopes: scopes_supported,
+            use_json_token_requests: false,
+        }
+    }
+
+    /// Space-separated `scope` parameter built from [`Self::scopes`]
+    pub fn scopes_str(&self) -> String {
+        self.scopes.join(" ")
+    }
+}